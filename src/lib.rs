@@ -1,7 +1,17 @@
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::serde::{Serialize, Deserialize};
-use near_sdk::{log, near, env, NearSchema};
-use near_sdk::store::LookupMap;
+use near_sdk::{log, near, env, AccountId, NearSchema};
+use near_sdk::store::{LookupMap, Vector};
+
+/// Height of the Merkle tree built by `attest_batch`. A height of 10
+/// yields `2^10 = 1024` leaves per batch.
+const TREE_HEIGHT: u32 = 10;
+
+/// Hard ceiling on the number of attestations a single session may
+/// accumulate, independent of any owner-configured `Policy`. Bounds the
+/// storage an attester can force the contract to pay for even when no
+/// policy has been set.
+const MAX_ATTESTATIONS_PER_SESSION: usize = 64;
 
 /// Represents a single assessment attestation stored on-chain.
 /// Contains the hash of the score payload, the block timestamp,
@@ -15,6 +25,179 @@ pub struct Attestation {
     pub attester: String,
 }
 
+/// Records that an attester signed two different `score_hash` values for
+/// the same `session_id`, along with both conflicting hashes and the
+/// timestamps at which each was submitted.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, NearSchema, Clone)]
+#[borsh(crate = "near_sdk::borsh")]
+#[serde(crate = "near_sdk::serde")]
+pub struct Equivocation {
+    pub attester: String,
+    pub session_id: String,
+    pub hash_a: String,
+    pub hash_b: String,
+    pub timestamp_a: u64,
+    pub timestamp_b: u64,
+}
+
+/// Configurable rules that gate `attest`. The contract owner sets the
+/// active policy via `set_policy`; a session's attestations must satisfy
+/// every configured rule before they are recorded.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, NearSchema, Clone)]
+#[borsh(crate = "near_sdk::borsh")]
+#[serde(crate = "near_sdk::serde")]
+pub struct Policy {
+    /// Minimum length a `score_hash` must have.
+    pub min_score_hash_len: u32,
+    /// If true, `score_hash` must consist solely of lowercase hex digits.
+    pub require_hex_format: bool,
+    /// If set, only these accounts may call `attest`.
+    pub allowed_attesters: Option<Vec<AccountId>>,
+    /// If set, a session may not accumulate more than this many
+    /// attestations in total.
+    pub max_attestations_per_session: Option<u32>,
+    /// If set, `attest` is only accepted while
+    /// `start <= env::block_timestamp() <= end`.
+    pub time_window: Option<(u64, u64)>,
+}
+
+/// Outcome of evaluating the active policy against a prospective
+/// attestation, without mutating contract state.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, NearSchema, Clone)]
+#[borsh(crate = "near_sdk::borsh")]
+#[serde(crate = "near_sdk::serde")]
+pub struct PolicyResult {
+    pub passed: bool,
+    pub reason: Option<String>,
+}
+
+/// A Merkle root committed on behalf of a batch of score hashes.
+/// Individual leaves are proven against this root via `verify_in_batch`
+/// instead of being stored on-chain themselves.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, NearSchema, Clone)]
+#[borsh(crate = "near_sdk::borsh")]
+#[serde(crate = "near_sdk::serde")]
+pub struct BatchRoot {
+    pub root: String,
+    pub timestamp: u64,
+    pub attester: String,
+}
+
+/// Hex-encode raw bytes without pulling in an extra dependency.
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Storage prefix for an individual attester's session index `Vector`,
+/// namespaced so each attester gets its own collection.
+///
+/// Uses a leading byte (`x`) that is never used as a top-level collection
+/// prefix on `Contract`, then hashes the account id rather than embedding
+/// it verbatim, so no attester's nested prefix can collide with another
+/// attester's prefix or with one of the contract's own top-level prefixes
+/// (`a`, `b`, `e`, `o`, `u`, `s`, `i`).
+fn attester_index_prefix(attester: &AccountId) -> Vec<u8> {
+    let mut prefix = b"x".to_vec();
+    prefix.extend_from_slice(&env::sha256(attester.as_bytes()));
+    prefix
+}
+
+/// `sha256(left || right)` where both inputs are hex-encoded hashes,
+/// returning the parent node as a hex-encoded hash.
+fn hash_pair(left: &str, right: &str) -> String {
+    let mut data = Vec::with_capacity(left.len() + right.len());
+    data.extend_from_slice(left.as_bytes());
+    data.extend_from_slice(right.as_bytes());
+    to_hex(&env::sha256(&data))
+}
+
+/// Build a fixed-height Merkle tree over `leaves`, padding with
+/// `zero_hash` up to `2^TREE_HEIGHT` leaves, and return the root.
+fn merkle_root(leaves: &[String], zero_hash: &str) -> String {
+    let width = 1usize << TREE_HEIGHT;
+    assert!(
+        leaves.len() <= width,
+        "batch exceeds maximum of {} leaves for tree height {}",
+        width,
+        TREE_HEIGHT
+    );
+
+    let mut level: Vec<String> = leaves.to_vec();
+    level.resize(width, zero_hash.to_string());
+
+    while level.len() > 1 {
+        level = level
+            .chunks(2)
+            .map(|pair| hash_pair(&pair[0], &pair[1]))
+            .collect();
+    }
+
+    level.into_iter().next().unwrap_or_else(|| zero_hash.to_string())
+}
+
+/// Evaluate `policy` against a prospective attestation, returning the
+/// first rule that fails, if any.
+fn check_policy(policy: &Policy, session_id: &str, score_hash: &str, existing_count: usize) -> PolicyResult {
+    if (score_hash.len() as u32) < policy.min_score_hash_len {
+        return PolicyResult {
+            passed: false,
+            reason: Some(format!(
+                "score_hash shorter than minimum length {}",
+                policy.min_score_hash_len
+            )),
+        };
+    }
+
+    let is_lowercase_hex = |c: char| c.is_ascii_digit() || c.is_ascii_lowercase() && c.is_ascii_hexdigit();
+    if policy.require_hex_format && !score_hash.chars().all(is_lowercase_hex) {
+        return PolicyResult {
+            passed: false,
+            reason: Some("score_hash must be lowercase hex".to_string()),
+        };
+    }
+
+    if let Some(allowed) = &policy.allowed_attesters {
+        let caller = env::predecessor_account_id();
+        if !allowed.contains(&caller) {
+            return PolicyResult {
+                passed: false,
+                reason: Some(format!("{} is not an allow-listed attester", caller)),
+            };
+        }
+    }
+
+    if let Some(max) = policy
+        .max_attestations_per_session
+        .filter(|&max| existing_count as u32 >= max)
+    {
+        return PolicyResult {
+            passed: false,
+            reason: Some(format!(
+                "session {} already has the maximum of {} attestations",
+                session_id, max
+            )),
+        };
+    }
+
+    if let Some((start, end)) = policy.time_window {
+        let now = env::block_timestamp();
+        if now < start || now > end {
+            return PolicyResult {
+                passed: false,
+                reason: Some(format!(
+                    "block timestamp {} is outside the policy window [{}, {}]",
+                    now, start, end
+                )),
+            };
+        }
+    }
+
+    PolicyResult {
+        passed: true,
+        reason: None,
+    }
+}
+
 /// PAICE Assessment Attestation Contract
 ///
 /// Stores SHA-256 hashes of assessment score payloads on NEAR testnet
@@ -23,8 +206,16 @@ pub struct Attestation {
 /// have not been modified after the fact.
 #[near(contract_state)]
 pub struct Contract {
-    attestations: LookupMap<String, Attestation>,
+    attestations: LookupMap<String, Vec<Attestation>>,
     attestation_count: u64,
+    batch_roots: LookupMap<String, BatchRoot>,
+    equivocations: LookupMap<String, Vec<Equivocation>>,
+    owners: LookupMap<String, AccountId>,
+    authorized_attesters: LookupMap<String, Vec<AccountId>>,
+    policy: Option<Policy>,
+    policy_owner: Option<AccountId>,
+    session_index: Vector<String>,
+    attester_index: LookupMap<AccountId, Vector<String>>,
 }
 
 impl Default for Contract {
@@ -32,51 +223,699 @@ impl Default for Contract {
         Self {
             attestations: LookupMap::new(b"a"),
             attestation_count: 0,
+            batch_roots: LookupMap::new(b"b"),
+            equivocations: LookupMap::new(b"e"),
+            owners: LookupMap::new(b"o"),
+            authorized_attesters: LookupMap::new(b"u"),
+            policy: None,
+            policy_owner: None,
+            session_index: Vector::new(b"s"),
+            attester_index: LookupMap::new(b"i"),
         }
     }
 }
 
 #[near]
 impl Contract {
+    /// Initialize the contract, setting `owner_id` as the policy owner.
+    /// Use this instead of relying on `Default` so the policy owner is
+    /// fixed atomically at deploy time rather than by whichever account
+    /// happens to call `set_policy` first.
+    #[init]
+    pub fn new(owner_id: AccountId) -> Self {
+        Self {
+            policy_owner: Some(owner_id),
+            ..Self::default()
+        }
+    }
+
     /// Store an assessment attestation on-chain.
     ///
     /// # Arguments
     /// * `session_id` - Unique identifier for the assessment session
     /// * `score_hash` - SHA-256 hash of the canonical score payload
     ///
-    /// The attestation records the hash, the block timestamp, and the
-    /// calling account as the attester.
+    /// Multiple independent attesters may attest to the same session;
+    /// each attestation records the hash, the block timestamp, and the
+    /// calling account. A given attester cannot submit the same
+    /// `(attester, score_hash)` pair twice for a session, but distinct
+    /// attesters may each attest independently, allowing agreement to be
+    /// measured via `get_agreement` and `is_finalized`. A session may
+    /// accumulate at most `MAX_ATTESTATIONS_PER_SESSION` attestations;
+    /// an owner-configured `Policy::max_attestations_per_session` can
+    /// lower that ceiling further but never raise it.
     pub fn attest(&mut self, session_id: String, score_hash: String) {
         assert!(!session_id.is_empty(), "session_id cannot be empty");
         assert!(!score_hash.is_empty(), "score_hash cannot be empty");
 
+        let caller = env::predecessor_account_id();
+        if let Some(owner) = self.owners.get(&session_id) {
+            let is_owner = &caller == owner;
+            let is_authorized = self
+                .authorized_attesters
+                .get(&session_id)
+                .map(|list| list.contains(&caller))
+                .unwrap_or(false);
+            assert!(
+                is_owner || is_authorized,
+                "{} is not authorized to attest for session {}",
+                caller,
+                session_id
+            );
+        }
+
+        if let Some(policy) = &self.policy {
+            let existing_count = self.attestations.get(&session_id).map(Vec::len).unwrap_or(0);
+            let result = check_policy(policy, &session_id, &score_hash, existing_count);
+            assert!(
+                result.passed,
+                "{}",
+                result.reason.unwrap_or_else(|| "attestation rejected by policy".to_string())
+            );
+        }
+
+        let attester = caller.to_string();
+        let mut records = self.attestations.get(&session_id).cloned().unwrap_or_default();
+        let is_new_session = records.is_empty();
+
+        assert!(
+            records.len() < MAX_ATTESTATIONS_PER_SESSION,
+            "session {} has reached the maximum of {} attestations",
+            session_id,
+            MAX_ATTESTATIONS_PER_SESSION
+        );
+
+        assert!(
+            !records
+                .iter()
+                .any(|r| r.attester == attester && r.score_hash == score_hash),
+            "attester {} already submitted this score_hash for session {}",
+            attester,
+            session_id
+        );
+
+        let timestamp = env::block_timestamp();
+
+        if let Some(prior) = records
+            .iter()
+            .find(|r| r.attester == attester && r.score_hash != score_hash)
+        {
+            let equivocation = Equivocation {
+                attester: attester.clone(),
+                session_id: session_id.clone(),
+                hash_a: prior.score_hash.clone(),
+                hash_b: score_hash.clone(),
+                timestamp_a: prior.timestamp,
+                timestamp_b: timestamp,
+            };
+
+            let mut entries = self.equivocations.get(&attester).cloned().unwrap_or_default();
+            entries.push(equivocation);
+            self.equivocations.insert(attester.clone(), entries);
+
+            log!(
+                "Equivocation detected for attester: {}, session: {}, hashes: {} / {}",
+                attester,
+                session_id,
+                prior.score_hash,
+                score_hash
+            );
+        }
+
         let attestation = Attestation {
             score_hash: score_hash.clone(),
-            timestamp: env::block_timestamp(),
-            attester: env::predecessor_account_id().to_string(),
+            timestamp,
+            attester: attester.clone(),
         };
 
-        self.attestations.insert(session_id.clone(), attestation);
+        records.push(attestation);
+        self.attestations.insert(session_id.clone(), records);
         self.attestation_count += 1;
 
+        if is_new_session {
+            self.session_index.push(session_id.clone());
+        }
+
+        match self.attester_index.get_mut(&caller) {
+            Some(sessions) => {
+                if !sessions.iter().any(|s| s == &session_id) {
+                    sessions.push(session_id.clone());
+                }
+            }
+            None => {
+                let mut sessions = Vector::new(attester_index_prefix(&caller));
+                sessions.push(session_id.clone());
+                self.attester_index.insert(caller.clone(), sessions);
+            }
+        }
+
         log!(
-            "Attestation stored for session: {}, hash: {}",
+            "Attestation stored for session: {}, hash: {}, attester: {}",
             session_id,
-            score_hash
+            score_hash,
+            attester
         );
     }
 
-    /// Verify an attestation by session ID.
+    /// Verify the attestations submitted for a session ID.
     ///
-    /// Returns the attestation data if found, or None if no attestation
-    /// exists for the given session ID. Callers can compare the returned
-    /// score_hash against a locally-computed hash to verify integrity.
-    pub fn verify(&self, session_id: String) -> Option<Attestation> {
-        self.attestations.get(&session_id).cloned()
+    /// Returns every attestation recorded for the given session id, one
+    /// per attester (or per conflicting submission), or an empty vector
+    /// if no attestation exists. Callers can compare the returned
+    /// score_hashes against a locally-computed hash to verify integrity.
+    pub fn verify(&self, session_id: String) -> Vec<Attestation> {
+        self.attestations
+            .get(&session_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// For a session, return each distinct `score_hash` alongside the
+    /// number of distinct attesters who submitted it. Attesters who have
+    /// been slashed for equivocation (see `is_slashed`) are not counted.
+    pub fn get_agreement(&self, session_id: String) -> Vec<(String, u32)> {
+        let records = match self.attestations.get(&session_id) {
+            Some(records) => records,
+            None => return Vec::new(),
+        };
+
+        let mut counts: Vec<(String, u32)> = Vec::new();
+        for record in records {
+            if self.is_slashed(record.attester.clone()) {
+                continue;
+            }
+            match counts.iter_mut().find(|(hash, _)| hash == &record.score_hash) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((record.score_hash.clone(), 1)),
+            }
+        }
+        counts
+    }
+
+    /// Return every recorded equivocation for a given attester.
+    pub fn get_equivocations(&self, attester: String) -> Vec<Equivocation> {
+        self.equivocations
+            .get(&attester)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Returns true if the given attester has ever signed two different
+    /// `score_hash` values for the same session.
+    pub fn is_slashed(&self, attester: String) -> bool {
+        self.equivocations
+            .get(&attester)
+            .map(|entries| !entries.is_empty())
+            .unwrap_or(false)
+    }
+
+    /// Returns true once some single `score_hash` for `session_id` has
+    /// been submitted by at least `threshold` distinct attesters. Slashed
+    /// attesters are not counted (see `get_agreement`).
+    pub fn is_finalized(&self, session_id: String, threshold: u32) -> bool {
+        self.get_agreement(session_id)
+            .into_iter()
+            .any(|(_, count)| count >= threshold)
     }
 
     /// Get the total number of attestations stored in this contract.
     pub fn get_attestation_count(&self) -> u64 {
         self.attestation_count
     }
+
+    /// Paginate over every `(session_id, Attestation)` pair across all
+    /// sessions, flattened in session-registration order and then in
+    /// per-session attestation order.
+    ///
+    /// # Arguments
+    /// * `from_index` - Index of the first `(session_id, Attestation)` pair to return
+    /// * `limit` - Maximum number of pairs to return
+    pub fn list_attestations(&self, from_index: u64, limit: u64) -> Vec<(String, Attestation)> {
+        self.session_index
+            .iter()
+            .flat_map(|session_id| {
+                self.attestations
+                    .get(session_id)
+                    .cloned()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|attestation| (session_id.clone(), attestation))
+            })
+            .skip(from_index as usize)
+            .take(limit as usize)
+            .collect()
+    }
+
+    /// Paginate over every `(session_id, Attestation)` pair a given
+    /// attester has submitted, flattened in the order they first attested
+    /// to each session and then in per-session submission order. Includes
+    /// every record the attester submitted for a session, not just the
+    /// first, so equivocating attesters' conflicting submissions both
+    /// appear.
+    ///
+    /// # Arguments
+    /// * `attester` - Account id of the attester to look up
+    /// * `from_index` - Index of the first `(session_id, Attestation)` pair to return
+    /// * `limit` - Maximum number of pairs to return
+    pub fn get_attestations_by_attester(
+        &self,
+        attester: String,
+        from_index: u64,
+        limit: u64,
+    ) -> Vec<(String, Attestation)> {
+        let attester_id: AccountId = attester
+            .parse()
+            .unwrap_or_else(|_| env::panic_str("invalid attester account id"));
+
+        let sessions = match self.attester_index.get(&attester_id) {
+            Some(sessions) => sessions,
+            None => return Vec::new(),
+        };
+
+        sessions
+            .iter()
+            .flat_map(|session_id| {
+                self.attestations
+                    .get(session_id)
+                    .cloned()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(|record| record.attester == attester)
+                    .map(|attestation| (session_id.clone(), attestation))
+            })
+            .skip(from_index as usize)
+            .take(limit as usize)
+            .collect()
+    }
+
+    /// Register the caller as the sole controller of `session_id`.
+    /// Callable once per session; once registered, `attest` for this
+    /// session is restricted to the owner and accounts it authorizes via
+    /// `authorize_attester`. Unregistered sessions remain open to any
+    /// attester for backward compatibility.
+    ///
+    /// Registration is self-service only: the caller always becomes the
+    /// owner, so a registration transaction cannot be front-run by an
+    /// attacker registering someone else's session out from under them.
+    pub fn register_session(&mut self, session_id: String) {
+        assert!(!session_id.is_empty(), "session_id cannot be empty");
+        assert!(
+            self.owners.get(&session_id).is_none(),
+            "session {} is already registered",
+            session_id
+        );
+
+        let owner = env::predecessor_account_id();
+        self.owners.insert(session_id.clone(), owner.clone());
+
+        log!("Session {} registered with owner {}", session_id, owner);
+    }
+
+    /// Authorize `attester` to call `attest` for `session_id`. Only the
+    /// registered owner of the session may grant authorization.
+    pub fn authorize_attester(&mut self, session_id: String, attester: AccountId) {
+        let owner = self
+            .owners
+            .get(&session_id)
+            .unwrap_or_else(|| env::panic_str(&format!("session {} is not registered", session_id)));
+
+        assert!(
+            &env::predecessor_account_id() == owner,
+            "only the session owner may authorize attesters"
+        );
+
+        let mut list = self
+            .authorized_attesters
+            .get(&session_id)
+            .cloned()
+            .unwrap_or_default();
+        if !list.contains(&attester) {
+            list.push(attester.clone());
+            self.authorized_attesters.insert(session_id.clone(), list);
+        }
+
+        log!("Authorized {} to attest for session {}", attester, session_id);
+    }
+
+    /// Set the active acceptance policy that gates future `attest` calls.
+    /// Only the policy owner fixed at deploy time (see `new`) may call
+    /// this.
+    pub fn set_policy(&mut self, policy: Policy) {
+        let caller = env::predecessor_account_id();
+        let owner = self
+            .policy_owner
+            .as_ref()
+            .unwrap_or_else(|| env::panic_str("contract has no policy owner; deploy via new(owner_id)"));
+
+        assert!(
+            &caller == owner,
+            "only the policy owner may update the policy"
+        );
+
+        self.policy = Some(policy);
+        log!("Policy updated by {}", caller);
+    }
+
+    /// Evaluate a prospective `attest(session_id, score_hash)` call from
+    /// the current predecessor against the acceptance gates of `attest`,
+    /// without mutating state: the session's owner/authorization
+    /// restriction (if registered), the `MAX_ATTESTATIONS_PER_SESSION`
+    /// hard cap, and the active owner-configured `Policy` (if any).
+    /// Returns `passed: true` only if all three would let the attestation
+    /// through. Empty arguments and a duplicate `(attester, score_hash)`
+    /// pair, which `attest` also rejects, are not checked here.
+    pub fn evaluate_policy(&self, session_id: String, score_hash: String) -> PolicyResult {
+        let caller = env::predecessor_account_id();
+        if let Some(owner) = self.owners.get(&session_id) {
+            let is_owner = &caller == owner;
+            let is_authorized = self
+                .authorized_attesters
+                .get(&session_id)
+                .map(|list| list.contains(&caller))
+                .unwrap_or(false);
+            if !is_owner && !is_authorized {
+                return PolicyResult {
+                    passed: false,
+                    reason: Some(format!(
+                        "{} is not authorized to attest for session {}",
+                        caller, session_id
+                    )),
+                };
+            }
+        }
+
+        let existing_count = self.attestations.get(&session_id).map(Vec::len).unwrap_or(0);
+        if existing_count >= MAX_ATTESTATIONS_PER_SESSION {
+            return PolicyResult {
+                passed: false,
+                reason: Some(format!(
+                    "session {} has reached the maximum of {} attestations",
+                    session_id, MAX_ATTESTATIONS_PER_SESSION
+                )),
+            };
+        }
+
+        match &self.policy {
+            Some(policy) => check_policy(policy, &session_id, &score_hash, existing_count),
+            None => PolicyResult {
+                passed: true,
+                reason: None,
+            },
+        }
+    }
+
+    /// Store a Merkle root over many score hashes in a single transaction.
+    ///
+    /// # Arguments
+    /// * `batch_id` - Unique identifier for the batch
+    /// * `score_hashes` - Ordered list of SHA-256 leaf hashes to commit
+    ///
+    /// Builds a fixed-height Merkle tree (see `TREE_HEIGHT`), padding with
+    /// a constant zero-hash leaf, and stores only the resulting root plus
+    /// the block timestamp and attester. Individual leaves are later
+    /// proven against the stored root via `verify_in_batch`.
+    pub fn attest_batch(&mut self, batch_id: String, score_hashes: Vec<String>) {
+        assert!(!batch_id.is_empty(), "batch_id cannot be empty");
+        assert!(!score_hashes.is_empty(), "score_hashes cannot be empty");
+
+        let zero_hash = to_hex(&[0u8; 32]);
+        let root = merkle_root(&score_hashes, &zero_hash);
+
+        let batch_root = BatchRoot {
+            root: root.clone(),
+            timestamp: env::block_timestamp(),
+            attester: env::predecessor_account_id().to_string(),
+        };
+
+        self.batch_roots.insert(batch_id.clone(), batch_root);
+
+        log!(
+            "Batch root stored for batch: {}, root: {}, leaves: {}",
+            batch_id,
+            root,
+            score_hashes.len()
+        );
+    }
+
+    /// Verify that `leaf_hash` at `leaf_index` is included in the batch
+    /// identified by `batch_id`, given its sibling path `proof`.
+    ///
+    /// Recomputes the root by hashing `leaf_hash` up the tree with each
+    /// sibling in `proof`, using the bits of `leaf_index` to decide
+    /// left/right order at each level, and compares it against the
+    /// stored root.
+    pub fn verify_in_batch(
+        &self,
+        batch_id: String,
+        leaf_hash: String,
+        leaf_index: u32,
+        proof: Vec<String>,
+    ) -> bool {
+        let stored = match self.batch_roots.get(&batch_id) {
+            Some(batch_root) => batch_root,
+            None => return false,
+        };
+
+        // A leaf of the fixed-height tree needs exactly one sibling per
+        // level and an index within the tree's width.
+        if proof.len() != TREE_HEIGHT as usize || leaf_index >= (1u32 << TREE_HEIGHT) {
+            return false;
+        }
+
+        let mut node = leaf_hash;
+        let mut index = leaf_index;
+        for sibling in proof.iter() {
+            node = if index.is_multiple_of(2) {
+                hash_pair(&node, sibling)
+            } else {
+                hash_pair(sibling, &node)
+            };
+            index /= 2;
+        }
+
+        node == stored.root
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::VMContextBuilder;
+    use near_sdk::testing_env;
+
+    fn set_caller(account_id: &str) {
+        let mut context = VMContextBuilder::new();
+        context.predecessor_account_id(account_id.parse().unwrap());
+        testing_env!(context.build());
+    }
+
+    /// Compute the sibling path for `leaves[index]`, mirroring how an
+    /// off-chain client would derive the proof passed to
+    /// `verify_in_batch`.
+    fn merkle_proof(leaves: &[String], zero_hash: &str, index: usize) -> Vec<String> {
+        let width = 1usize << TREE_HEIGHT;
+        let mut level: Vec<String> = leaves.to_vec();
+        level.resize(width, zero_hash.to_string());
+
+        let mut proof = Vec::new();
+        let mut idx = index;
+        while level.len() > 1 {
+            proof.push(level[idx ^ 1].clone());
+            level = level
+                .chunks(2)
+                .map(|pair| hash_pair(&pair[0], &pair[1]))
+                .collect();
+            idx /= 2;
+        }
+        proof
+    }
+
+    #[test]
+    fn attest_batch_verifies_inclusion_via_proof() {
+        set_caller("alice.testnet");
+        let mut contract = Contract::default();
+
+        let leaves = vec!["aa".repeat(32), "bb".repeat(32), "cc".repeat(32)];
+        let zero_hash = to_hex(&[0u8; 32]);
+        contract.attest_batch("batch-1".to_string(), leaves.clone());
+
+        let proof = merkle_proof(&leaves, &zero_hash, 1);
+        assert!(contract.verify_in_batch("batch-1".to_string(), leaves[1].clone(), 1, proof));
+    }
+
+    #[test]
+    fn verify_in_batch_rejects_wrong_index_and_wrong_leaf() {
+        set_caller("alice.testnet");
+        let mut contract = Contract::default();
+
+        let leaves = vec!["aa".repeat(32), "bb".repeat(32), "cc".repeat(32)];
+        let zero_hash = to_hex(&[0u8; 32]);
+        contract.attest_batch("batch-1".to_string(), leaves.clone());
+
+        let proof = merkle_proof(&leaves, &zero_hash, 1);
+
+        // The same proof at the wrong leaf_index recomputes a different root.
+        assert!(!contract.verify_in_batch("batch-1".to_string(), leaves[1].clone(), 2, proof.clone()));
+
+        // A leaf hash that was never committed should not verify.
+        assert!(!contract.verify_in_batch("batch-1".to_string(), "dd".repeat(32), 1, proof));
+
+        // An unknown batch_id never verifies.
+        assert!(!contract.verify_in_batch(
+            "no-such-batch".to_string(),
+            leaves[1].clone(),
+            1,
+            merkle_proof(&leaves, &zero_hash, 1)
+        ));
+    }
+
+    #[test]
+    fn quorum_reaches_finalization_once_threshold_agrees() {
+        set_caller("alice.testnet");
+        let mut contract = Contract::default();
+        contract.attest("session-1".to_string(), "hash-a".to_string());
+
+        set_caller("bob.testnet");
+        contract.attest("session-1".to_string(), "hash-a".to_string());
+
+        assert!(contract.is_finalized("session-1".to_string(), 2));
+        assert!(!contract.is_finalized("session-1".to_string(), 3));
+    }
+
+    #[test]
+    #[should_panic(expected = "already submitted this score_hash")]
+    fn duplicate_attester_hash_pair_is_rejected() {
+        set_caller("alice.testnet");
+        let mut contract = Contract::default();
+        contract.attest("session-1".to_string(), "hash-a".to_string());
+        contract.attest("session-1".to_string(), "hash-a".to_string());
+    }
+
+    #[test]
+    fn equivocation_is_recorded_and_excluded_from_agreement() {
+        set_caller("alice.testnet");
+        let mut contract = Contract::default();
+        contract.attest("session-1".to_string(), "hash-a".to_string());
+
+        set_caller("bob.testnet");
+        contract.attest("session-1".to_string(), "hash-a".to_string());
+        contract.attest("session-1".to_string(), "hash-b".to_string());
+
+        assert!(contract.is_slashed("bob.testnet".to_string()));
+        assert_eq!(contract.get_equivocations("bob.testnet".to_string()).len(), 1);
+
+        let agreement = contract.get_agreement("session-1".to_string());
+        let hash_a_count = agreement
+            .iter()
+            .find(|(hash, _)| hash == "hash-a")
+            .map(|(_, count)| *count)
+            .unwrap_or(0);
+        assert_eq!(hash_a_count, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "is already registered")]
+    fn register_session_rejects_double_registration() {
+        set_caller("alice.testnet");
+        let mut contract = Contract::default();
+        contract.register_session("session-1".to_string());
+        contract.register_session("session-1".to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "is not authorized to attest")]
+    fn attest_rejects_unauthorized_caller_on_registered_session() {
+        set_caller("alice.testnet");
+        let mut contract = Contract::default();
+        contract.register_session("session-1".to_string());
+
+        set_caller("mallory.testnet");
+        contract.attest("session-1".to_string(), "hash-a".to_string());
+    }
+
+    #[test]
+    fn authorize_attester_allows_attest_after_grant() {
+        set_caller("alice.testnet");
+        let mut contract = Contract::default();
+        contract.register_session("session-1".to_string());
+        contract.authorize_attester("session-1".to_string(), "bob.testnet".parse().unwrap());
+
+        set_caller("bob.testnet");
+        contract.attest("session-1".to_string(), "hash-a".to_string());
+        assert_eq!(contract.verify("session-1".to_string()).len(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "only the session owner may authorize attesters")]
+    fn authorize_attester_rejects_non_owner_caller() {
+        set_caller("alice.testnet");
+        let mut contract = Contract::default();
+        contract.register_session("session-1".to_string());
+
+        set_caller("mallory.testnet");
+        contract.authorize_attester("session-1".to_string(), "bob.testnet".parse().unwrap());
+    }
+
+    #[test]
+    fn list_attestations_paginates_by_item_across_sessions() {
+        set_caller("alice.testnet");
+        let mut contract = Contract::default();
+        contract.attest("session-1".to_string(), "hash-a".to_string());
+        contract.attest("session-1".to_string(), "hash-b".to_string());
+        contract.attest("session-2".to_string(), "hash-c".to_string());
+
+        // session-1 alone contributes two items; limit=1 must return only
+        // the first item, not skip straight past session-1 entirely.
+        let page = contract.list_attestations(0, 1);
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].0, "session-1");
+        assert_eq!(page[0].1.score_hash, "hash-a");
+
+        let rest = contract.list_attestations(1, 10);
+        assert_eq!(rest.len(), 2);
+        assert_eq!(rest[0].1.score_hash, "hash-b");
+        assert_eq!(rest[1].0, "session-2");
+    }
+
+    #[test]
+    fn get_attestations_by_attester_returns_every_matching_record() {
+        set_caller("alice.testnet");
+        let mut contract = Contract::default();
+        contract.attest("session-1".to_string(), "hash-a".to_string());
+        contract.attest("session-1".to_string(), "hash-b".to_string());
+        contract.attest("session-2".to_string(), "hash-c".to_string());
+
+        // alice equivocated on session-1 (hash-a then hash-b); both of her
+        // submissions for that session must be returned, not just the first.
+        let all = contract.get_attestations_by_attester("alice.testnet".to_string(), 0, 10);
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[0].1.score_hash, "hash-a");
+        assert_eq!(all[1].1.score_hash, "hash-b");
+        assert_eq!(all[2].0, "session-2");
+
+        let page = contract.get_attestations_by_attester("alice.testnet".to_string(), 1, 1);
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].1.score_hash, "hash-b");
+    }
+
+    #[test]
+    fn check_policy_rejects_short_and_uppercase_hashes() {
+        set_caller("owner.testnet");
+        let mut contract = Contract::new("owner.testnet".parse().unwrap());
+        contract.set_policy(Policy {
+            min_score_hash_len: 8,
+            require_hex_format: true,
+            allowed_attesters: None,
+            max_attestations_per_session: None,
+            time_window: None,
+        });
+
+        assert!(!contract.evaluate_policy("session-2".to_string(), "ab".to_string()).passed);
+        assert!(
+            !contract
+                .evaluate_policy("session-2".to_string(), "ABCDEFAB".to_string())
+                .passed
+        );
+        assert!(contract.evaluate_policy("session-2".to_string(), "abcdefab".to_string()).passed);
+    }
 }